@@ -1,9 +1,16 @@
 use std::collections::HashMap;
+use std::fmt;
 
-use crate::types::hash::IHashEntity;
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::types::digest::DHash256;
+use crate::types::hash::{HashKind, IHashEntity};
+use crate::types::merkle::{self, ProofStep};
 
 
-// Add Serialize/Deserialize from JSON
 #[derive(Debug, Clone)]
 pub enum JsonValue {
   String(String),
@@ -12,9 +19,130 @@ pub enum JsonValue {
   Null,
 }
 
+impl From<JsonValue> for serde_json::Value {
+  fn from(value: JsonValue) -> Self {
+    match value {
+      JsonValue::String(s) => serde_json::Value::String(s),
+      JsonValue::Number(n) => serde_json::Number::from_f64(n)
+        .map(serde_json::Value::Number)
+        .unwrap_or(serde_json::Value::Null),
+      JsonValue::Bool(b) => serde_json::Value::Bool(b),
+      JsonValue::Null => serde_json::Value::Null,
+    }
+  }
+}
+
+impl TryFrom<serde_json::Value> for JsonValue {
+  type Error = String;
 
-// Add Serialize/Deserialize from JSON
-#[derive(Debug, Clone)]
+  fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+    match value {
+      serde_json::Value::String(s) => Ok(JsonValue::String(s)),
+      serde_json::Value::Number(n) => n
+        .as_f64()
+        .map(JsonValue::Number)
+        .ok_or_else(|| format!("number '{}' is not representable as f64", n)),
+      serde_json::Value::Bool(b) => Ok(JsonValue::Bool(b)),
+      serde_json::Value::Null => Ok(JsonValue::Null),
+      other => Err(format!("unsupported JSON value '{}'", other)),
+    }
+  }
+}
+
+// Round-trips through `serde_json::Value` so `metadata` can be loaded from
+// and dumped to JSON. Strings, bools, null and finite numbers survive the
+// trip; non-finite numbers are written as `null`, integers are read as `f64`
+// (so ones beyond 2^53 lose precision), and arrays and objects are rejected.
+impl Serialize for JsonValue {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serde_json::Value::from(self.clone()).serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for JsonValue {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    JsonValue::try_from(value).map_err(de::Error::custom)
+  }
+}
+
+/// `metadata_hash` and `JsonValue::representation_hash` only fail on
+/// non-finite numbers, which have no canonical byte representation.
+#[derive(Debug)]
+pub struct NonFiniteNumber;
+
+impl fmt::Display for NonFiniteNumber {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("cannot canonically encode a non-finite number")
+  }
+}
+
+impl std::error::Error for NonFiniteNumber {}
+
+impl JsonValue {
+  /// Encodes this value the same way regardless of where it came from:
+  /// strings as UTF-8, bools as a single byte, null as nothing, and
+  /// numbers as their big-endian IEEE-754 bytes.
+  pub fn canonical_encode(&self) -> Result<Vec<u8>, NonFiniteNumber> {
+    match self {
+      JsonValue::String(s) => Ok(s.as_bytes().to_vec()),
+      JsonValue::Bool(b) => Ok(vec![*b as u8]),
+      JsonValue::Null => Ok(Vec::new()),
+      JsonValue::Number(n) => {
+        if !n.is_finite() {
+          return Err(NonFiniteNumber);
+        }
+        Ok(n.to_be_bytes().to_vec())
+      }
+    }
+  }
+
+  /// `sha256` of [`JsonValue::canonical_encode`], usable as a
+  /// representation-independent fingerprint of this value.
+  pub fn representation_hash(&self) -> Result<IHashEntity, NonFiniteNumber> {
+    let encoded = self.canonical_encode()?;
+    let digest = Sha256::digest(&encoded);
+    Ok(IHashEntity::new_checked(HashKind::Sha256, digest.to_vec())
+      .expect("sha256 digest is always 32 bytes"))
+  }
+}
+
+/// Order-independent hash of a metadata map: each `(key, value)` pair is
+/// reduced to a 64-byte `sha256(key) || sha256(encode(value))` record, the
+/// records are sorted lexicographically, and the sorted, concatenated
+/// records are hashed. Two maps with the same entries hash the same
+/// regardless of insertion order.
+pub fn metadata_hash(metadata: &HashMap<String, JsonValue>) -> Result<IHashEntity, NonFiniteNumber> {
+  let mut records: Vec<[u8; 64]> = Vec::with_capacity(metadata.len());
+  for (key, value) in metadata {
+    let key_hash = Sha256::digest(key.as_bytes());
+    let value_hash = Sha256::digest(value.canonical_encode()?);
+
+    let mut record = [0u8; 64];
+    record[..32].copy_from_slice(&key_hash);
+    record[32..].copy_from_slice(&value_hash);
+    records.push(record);
+  }
+
+  records.sort_unstable();
+
+  let mut hasher = Sha256::new();
+  for record in &records {
+    hasher.update(record);
+  }
+
+  Ok(IHashEntity::new_checked(HashKind::Sha256, hasher.finalize().to_vec())
+    .expect("sha256 digest is always 32 bytes"))
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockHeaders {
   pub ts:u64,
   pub timestamp: String,
@@ -22,19 +150,22 @@ pub struct BlockHeaders {
   pub merkle_root: String,
   pub version: u32,
   pub nonce: u64,
+  /// Proof-of-work difficulty this block was mined at.
+  pub difficulty: u32,
+  /// Bumped whenever `nonce` wraps around, so mining can keep searching
+  /// past the full `u64` nonce space.
+  pub extra_nonce: u64,
 }
 
 
-// Add Serialize/Deserialize from JSON
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction<T> {
   pub payload: T,
   pub sequence: u64,
 }
 
 
-// Add Serialize/Deserialize from JSON
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Block<T> {
   pub _id: String,
   pub public_block_id: String,
@@ -45,4 +176,63 @@ pub struct Block<T> {
   pub metadata: HashMap<String, JsonValue>,
   pub content_signature: IHashEntity,
   pub block_signature: IHashEntity,
+}
+
+impl<T> Block<T>
+where
+  T: Serialize,
+{
+  /// Hashes every transaction into a single double-SHA256 digest as they are
+  /// read, so no second pass over `transactions` is needed.
+  pub fn compute_content_signature(&self) -> IHashEntity {
+    let mut hasher = DHash256::new();
+    for transaction in &self.transactions {
+      let bytes =
+        serde_json::to_vec(transaction).expect("transaction serializes to JSON");
+      hasher.update(&bytes);
+    }
+    hasher.finish()
+  }
+
+  /// Hashes the header fields, `previous_hash`, the content signature, and
+  /// the order-independent metadata hash together, sealing the block.
+  pub fn compute_block_signature(&self) -> Result<IHashEntity, NonFiniteNumber> {
+    let mut hasher = DHash256::new();
+    hasher.update(&self.headers.version.to_be_bytes());
+    hasher.update(&self.headers.ts.to_be_bytes());
+    hasher.update(self.headers.timestamp.as_bytes());
+    hasher.update(&(self.headers.content_length as u64).to_be_bytes());
+    hasher.update(self.headers.merkle_root.as_bytes());
+    hasher.update(&self.headers.nonce.to_be_bytes());
+    hasher.update(&self.headers.extra_nonce.to_be_bytes());
+    hasher.update(&self.headers.difficulty.to_be_bytes());
+    hasher.update(&self.previous_hash.buffer());
+    hasher.update(&self.content_signature.buffer());
+    hasher.update(&metadata_hash(&self.metadata)?.buffer());
+    Ok(hasher.finish())
+  }
+
+  /// Builds the Merkle root over `transactions` and writes it into
+  /// `headers.merkle_root`.
+  pub fn compute_merkle_root(&mut self) -> IHashEntity {
+    let leaves: Vec<IHashEntity> = self
+      .transactions
+      .iter()
+      .map(merkle::transaction_leaf)
+      .collect();
+    let root = merkle::root(&leaves);
+    self.headers.merkle_root = root.digest(Some("hex"));
+    root
+  }
+
+  /// Builds an inclusion proof for the transaction at `index`, checkable
+  /// with [`merkle::verify_proof`] without the rest of the block.
+  pub fn merkle_proof(&self, index: usize) -> Vec<ProofStep> {
+    let leaves: Vec<IHashEntity> = self
+      .transactions
+      .iter()
+      .map(merkle::transaction_leaf)
+      .collect();
+    merkle::proof(&leaves, index)
+  }
 }
\ No newline at end of file
@@ -0,0 +1,34 @@
+use sha2::{Digest, Sha256};
+
+use crate::types::hash::{HashKind, IHashEntity};
+
+/// Bitcoin-style double SHA-256: `sha256(sha256(data))`.
+///
+/// Feed bytes incrementally with [`DHash256::update`], then call
+/// [`DHash256::finish`] to get the tagged 32-byte digest.
+pub struct DHash256 {
+  hasher: Sha256,
+}
+
+impl DHash256 {
+  pub fn new() -> Self {
+    DHash256 { hasher: Sha256::new() }
+  }
+
+  pub fn update(&mut self, data: &[u8]) {
+    self.hasher.update(data);
+  }
+
+  pub fn finish(self) -> IHashEntity {
+    let first_pass = self.hasher.finalize();
+    let second_pass = Sha256::digest(first_pass);
+    IHashEntity::new_checked(HashKind::Sha256, second_pass.to_vec())
+      .expect("sha256 digest is always 32 bytes")
+  }
+}
+
+impl Default for DHash256 {
+  fn default() -> Self {
+    Self::new()
+  }
+}
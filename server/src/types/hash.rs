@@ -1,18 +1,148 @@
 use std::fmt;
+use std::str::FromStr;
 use base64::engine::{general_purpose, Engine as _};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+
+/// The hashing algorithm that produced an [`IHashEntity`]'s bytes.
+///
+/// `Unknown` is the escape hatch for hashes constructed with [`IHashEntity::new`],
+/// where the caller hasn't (or can't) say which algorithm was used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashKind {
+  Unknown,
+  Sha256,
+  Sha1,
+  Ripemd160,
+  Blake3,
+  Sha512,
+}
+
+impl HashKind {
+  /// The digest length this algorithm always produces, or `None` for `Unknown`,
+  /// which carries no length invariant.
+  pub fn len_in_bytes(&self) -> Option<usize> {
+    match self {
+      HashKind::Unknown => None,
+      HashKind::Sha256 => Some(32),
+      HashKind::Sha1 => Some(20),
+      HashKind::Ripemd160 => Some(20),
+      HashKind::Blake3 => Some(32),
+      HashKind::Sha512 => Some(64),
+    }
+  }
+}
+
+impl TryFrom<u8> for HashKind {
+  type Error = HashError;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    match value {
+      0 => Ok(HashKind::Unknown),
+      1 => Ok(HashKind::Sha256),
+      2 => Ok(HashKind::Sha1),
+      3 => Ok(HashKind::Ripemd160),
+      4 => Ok(HashKind::Blake3),
+      5 => Ok(HashKind::Sha512),
+      other => Err(HashError::UnknownKindCode(other)),
+    }
+  }
+}
+
+impl FromStr for HashKind {
+  type Err = HashError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_ascii_lowercase().as_str() {
+      "unknown" => Ok(HashKind::Unknown),
+      "sha256" => Ok(HashKind::Sha256),
+      "sha1" => Ok(HashKind::Sha1),
+      "ripemd160" => Ok(HashKind::Ripemd160),
+      "blake3" => Ok(HashKind::Blake3),
+      "sha512" => Ok(HashKind::Sha512),
+      other => Err(HashError::UnknownKindName(other.to_string())),
+    }
+  }
+}
+
+impl fmt::Display for HashKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      HashKind::Unknown => "unknown",
+      HashKind::Sha256 => "sha256",
+      HashKind::Sha1 => "sha1",
+      HashKind::Ripemd160 => "ripemd160",
+      HashKind::Blake3 => "blake3",
+      HashKind::Sha512 => "sha512",
+    };
+    f.write_str(name)
+  }
+}
+
+
+#[derive(Debug)]
+pub enum HashError {
+  LengthMismatch {
+    kind: HashKind,
+    expected: usize,
+    actual: usize,
+  },
+  UnknownKindCode(u8),
+  UnknownKindName(String),
+  InvalidHex(String),
+}
+
+impl fmt::Display for HashError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      HashError::LengthMismatch { kind, expected, actual } => write!(
+        f,
+        "{} digest must be {} bytes, got {}",
+        kind, expected, actual
+      ),
+      HashError::UnknownKindCode(code) => write!(f, "unknown hash kind code {}", code),
+      HashError::UnknownKindName(name) => write!(f, "unknown hash kind '{}'", name),
+      HashError::InvalidHex(hex) => write!(f, "invalid hex string '{}'", hex),
+    }
+  }
+}
+
+impl std::error::Error for HashError {}
 
 
 #[derive(Clone)]
 pub struct IHashEntity {
   pub byte_length: u64,
+  pub kind: HashKind,
   data: Vec<u8>,
 }
 
 
 impl IHashEntity {
+  /// Builds a hash entity without checking its length against any algorithm,
+  /// tagging it `HashKind::Unknown`. Prefer [`IHashEntity::new_checked`] when
+  /// the producing algorithm is known.
   pub fn new(data: Vec<u8>) -> Self {
     let byte_length = data.len() as u64;
-    IHashEntity { byte_length, data }
+    IHashEntity { byte_length, kind: HashKind::Unknown, data }
+  }
+
+  /// Builds a hash entity, rejecting data whose length doesn't match what
+  /// `kind` is expected to produce.
+  pub fn new_checked(kind: HashKind, data: Vec<u8>) -> Result<Self, HashError> {
+    if let Some(expected) = kind.len_in_bytes() {
+      if data.len() != expected {
+        return Err(HashError::LengthMismatch {
+          kind,
+          expected,
+          actual: data.len(),
+        });
+      }
+    }
+
+    let byte_length = data.len() as u64;
+    Ok(IHashEntity { byte_length, kind, data })
   }
 
   pub fn digest(&self, encoding: Option<&str>) -> String {
@@ -35,13 +165,156 @@ impl IHashEntity {
   pub fn buffer(&self) -> Vec<u8> {
     self.data.clone()
   }
+
+  pub fn as_slice(&self) -> &[u8] {
+    &self.data
+  }
+
+  /// True when the hash is empty or made up entirely of zero bytes.
+  pub fn is_zero(&self) -> bool {
+    self.data.iter().all(|&b| b == 0)
+  }
+
+  /// The last 8 bytes, read as a big-endian `u64`. Useful for bucketing or
+  /// sharding by hash without pulling the whole digest around. Missing
+  /// leading bytes (for digests shorter than 8 bytes) are treated as zero.
+  pub fn tail_u64(&self) -> u64 {
+    let mut buf = [0u8; 8];
+    let tail = if self.data.len() >= 8 {
+      &self.data[self.data.len() - 8..]
+    } else {
+      &self.data[..]
+    };
+    buf[8 - tail.len()..].copy_from_slice(tail);
+    u64::from_be_bytes(buf)
+  }
 }
 
 impl fmt::Debug for IHashEntity {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result  {
     f.debug_struct("IHashEntity")
+      .field("kind", &self.kind)
       .field("byte_length", &self.byte_length)
       .field("digest", &self.digest(Some("hex")))
       .finish()
   }
 }
+
+// Wire format is the lowercase hex digest, not the raw bytes, so hashes read
+// naturally in JSON payloads and logs.
+impl Serialize for IHashEntity {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serializer.serialize_str(&self.digest(Some("hex")))
+  }
+}
+
+struct HexDigestVisitor;
+
+impl<'de> Visitor<'de> for HexDigestVisitor {
+  type Value = IHashEntity;
+
+  fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("a lowercase hex-encoded hash digest")
+  }
+
+  fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+  where
+    E: de::Error,
+  {
+    if v.len() % 2 != 0 {
+      return Err(E::custom(format!("odd-length hex string '{}'", v)));
+    }
+
+    let mut data = Vec::with_capacity(v.len() / 2);
+    for i in (0..v.len()).step_by(2) {
+      let byte = u8::from_str_radix(&v[i..i + 2], 16)
+        .map_err(|_| E::custom(format!("invalid hex digit in '{}'", v)))?;
+      data.push(byte);
+    }
+
+    Ok(IHashEntity::new(data))
+  }
+}
+
+impl<'de> Deserialize<'de> for IHashEntity {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    deserializer.deserialize_str(HexDigestVisitor)
+  }
+}
+
+
+/// A partial hash, precise to the nibble, for chain lookups and
+/// human-facing logs that reference a hash by a short prefix.
+#[derive(Debug, Clone)]
+pub struct HashPrefix {
+  bytes: Vec<u8>,
+  nibbles: usize,
+}
+
+impl HashPrefix {
+  /// Parses a hex string like `"48592043"` into a prefix. An odd number of
+  /// hex digits is allowed; the trailing half-byte is tracked and only its
+  /// high nibble is compared in [`HashPrefix::matches`].
+  pub fn from_hex(hex: &str) -> Result<Self, HashError> {
+    let nibbles = hex.len();
+    let mut bytes = Vec::with_capacity(nibbles.div_ceil(2));
+    let mut chars = hex.chars();
+
+    loop {
+      let Some(high) = chars.next() else { break };
+      let high_val = high
+        .to_digit(16)
+        .ok_or_else(|| HashError::InvalidHex(hex.to_string()))?;
+
+      match chars.next() {
+        Some(low) => {
+          let low_val = low
+            .to_digit(16)
+            .ok_or_else(|| HashError::InvalidHex(hex.to_string()))?;
+          bytes.push(((high_val << 4) | low_val) as u8);
+        }
+        None => bytes.push((high_val << 4) as u8),
+      }
+    }
+
+    Ok(HashPrefix { bytes, nibbles })
+  }
+
+  /// Whether `hash`'s leading nibbles equal this prefix's.
+  pub fn matches(&self, hash: &IHashEntity) -> bool {
+    let data = hash.as_slice();
+    let full_bytes = self.nibbles / 2;
+
+    if data.len() < full_bytes || self.bytes[..full_bytes] != data[..full_bytes] {
+      return false;
+    }
+
+    if self.nibbles % 2 == 1 {
+      match data.get(full_bytes) {
+        Some(byte) if (byte & 0xf0) == (self.bytes[full_bytes] & 0xf0) => {}
+        _ => return false,
+      }
+    }
+
+    true
+  }
+}
+
+impl fmt::Display for HashPrefix {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let full_bytes = self.nibbles / 2;
+    for byte in &self.bytes[..full_bytes] {
+      write!(f, "{:02x}", byte)?;
+    }
+    if self.nibbles % 2 == 1 {
+      write!(f, "{:x}", self.bytes[full_bytes] >> 4)?;
+    }
+    Ok(())
+  }
+}
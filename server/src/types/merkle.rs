@@ -0,0 +1,91 @@
+use serde::Serialize;
+
+use crate::types::block::Transaction;
+use crate::types::digest::DHash256;
+use crate::types::hash::{HashKind, IHashEntity};
+
+/// Hashes a single transaction into a Merkle leaf.
+pub fn transaction_leaf<T: Serialize>(transaction: &Transaction<T>) -> IHashEntity {
+  let mut hasher = DHash256::new();
+  let bytes = serde_json::to_vec(transaction).expect("transaction serializes to JSON");
+  hasher.update(&bytes);
+  hasher.finish()
+}
+
+fn parent_hash(left: &IHashEntity, right: &IHashEntity) -> IHashEntity {
+  let mut hasher = DHash256::new();
+  hasher.update(&left.buffer());
+  hasher.update(&right.buffer());
+  hasher.finish()
+}
+
+/// The all-zero hash used as the Merkle root of a block with no transactions.
+fn empty_root() -> IHashEntity {
+  IHashEntity::new_checked(HashKind::Sha256, vec![0u8; 32])
+    .expect("32 zero bytes is a valid sha256-shaped digest")
+}
+
+/// Builds a Merkle root from a slice of leaf hashes, duplicating the last
+/// node at any level with an odd number of nodes.
+pub fn root(leaves: &[IHashEntity]) -> IHashEntity {
+  if leaves.is_empty() {
+    return empty_root();
+  }
+
+  let mut level = leaves.to_vec();
+  while level.len() > 1 {
+    if level.len() % 2 == 1 {
+      level.push(level.last().expect("level is non-empty").clone());
+    }
+    level = level
+      .chunks(2)
+      .map(|pair| parent_hash(&pair[0], &pair[1]))
+      .collect();
+  }
+
+  level.into_iter().next().expect("level reduces to exactly one node")
+}
+
+/// A proof step: the sibling hash, and whether it sits to the right of the
+/// node being proven at that level.
+pub type ProofStep = (IHashEntity, bool);
+
+/// Builds an inclusion proof for the leaf at `index`, from the leaf's level
+/// up to (but not including) the root.
+pub fn proof(leaves: &[IHashEntity], index: usize) -> Vec<ProofStep> {
+  let mut steps = Vec::new();
+  let mut level = leaves.to_vec();
+  let mut idx = index;
+
+  while level.len() > 1 {
+    if level.len() % 2 == 1 {
+      level.push(level.last().expect("level is non-empty").clone());
+    }
+
+    let sibling_is_right = idx % 2 == 0;
+    let sibling_idx = if sibling_is_right { idx + 1 } else { idx - 1 };
+    steps.push((level[sibling_idx].clone(), sibling_is_right));
+
+    level = level
+      .chunks(2)
+      .map(|pair| parent_hash(&pair[0], &pair[1]))
+      .collect();
+    idx /= 2;
+  }
+
+  steps
+}
+
+/// Replays a proof produced by [`proof`] against `leaf` and checks it
+/// reproduces `expected_root`, without needing the rest of the tree.
+pub fn verify_proof(leaf: &IHashEntity, steps: &[ProofStep], expected_root: &IHashEntity) -> bool {
+  let mut current = leaf.clone();
+  for (sibling, sibling_is_right) in steps {
+    current = if *sibling_is_right {
+      parent_hash(&current, sibling)
+    } else {
+      parent_hash(sibling, &current)
+    };
+  }
+  current.buffer() == expected_root.buffer()
+}
@@ -0,0 +1,5 @@
+pub mod block;
+pub mod digest;
+pub mod hash;
+pub mod merkle;
+pub mod pow;
@@ -0,0 +1,57 @@
+use num_bigint::BigUint;
+use serde::Serialize;
+
+use crate::types::block::Block;
+
+/// Hash signatures are 256 bits wide; a difficulty of 256 would leave a
+/// target of 1, which only an all-zero signature meets, and anything above
+/// it has no target at all.
+pub const MAX_DIFFICULTY: usize = 256;
+
+/// The 256-bit target a block signature must fall under: `2^(256 - difficulty)`.
+pub fn target_for_difficulty(difficulty: usize) -> BigUint {
+  assert!(difficulty < MAX_DIFFICULTY, "difficulty must be < {}", MAX_DIFFICULTY);
+  BigUint::from(1u8) << (256 - difficulty)
+}
+
+impl<T> Block<T>
+where
+  T: Serialize,
+{
+  /// Whether this block's current `block_signature`, read as a big-endian
+  /// integer, falls under the target for `difficulty`.
+  pub fn meets_difficulty(&self, difficulty: usize) -> bool {
+    let target = target_for_difficulty(difficulty);
+    let value = BigUint::from_bytes_be(&self.block_signature.buffer());
+    value < target
+  }
+}
+
+/// Mines `block` at `difficulty`: repeatedly bumps `headers.nonce`,
+/// recomputing `block_signature` each time, until it falls under the
+/// difficulty target. If `nonce` wraps around `u64`, `headers.extra_nonce`
+/// is bumped and the search continues.
+pub fn mine<T>(block: &mut Block<T>, difficulty: usize)
+where
+  T: Serialize,
+{
+  assert!(difficulty < MAX_DIFFICULTY, "difficulty must be < {}", MAX_DIFFICULTY);
+  let target = target_for_difficulty(difficulty);
+  block.headers.difficulty = difficulty as u32;
+
+  loop {
+    block.block_signature = block
+      .compute_block_signature()
+      .expect("block metadata must encode to a finite canonical hash");
+    let value = BigUint::from_bytes_be(&block.block_signature.buffer());
+    if value < target {
+      return;
+    }
+
+    let (next_nonce, overflowed) = block.headers.nonce.overflowing_add(1);
+    block.headers.nonce = next_nonce;
+    if overflowed {
+      block.headers.extra_nonce = block.headers.extra_nonce.wrapping_add(1);
+    }
+  }
+}